@@ -0,0 +1,367 @@
+//! Utilities for working with the PostgreSQL binary copy format.
+#![warn(rust_2018_idioms, clippy::all, missing_docs)]
+
+use byteorder::{BigEndian, ByteOrder};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use futures::channel::mpsc;
+use futures::{ready, SinkExt, Stream};
+use pin_project_lite::pin_project;
+use std::convert::TryFrom;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio_postgres::types::{BorrowToSql, FromSql, IsNull, ToSql, Type, WrongType};
+use tokio_postgres::{CopyInSink, CopyOutStream, Error};
+
+#[cfg(test)]
+mod test;
+
+const MAGIC: &[u8] = b"PGCOPY\n\xff\r\n\0";
+const HEADER_LEN: usize = MAGIC.len() + 4 + 4;
+
+pin_project! {
+    /// A stream of `Bytes` containing a stream of rows in the PostgreSQL binary copy format.
+    ///
+    /// The type of each row is determined by the `types` slice passed to `new`; the provided
+    /// `f` is run against a writer which collects the encoded rows into the stream.
+    pub struct BinaryCopyInStream {
+        #[pin]
+        receiver: mpsc::Receiver<Bytes>,
+        #[pin]
+        driver: Pin<Box<dyn Future<Output = Result<(), Error>> + Send>>,
+        done: bool,
+    }
+}
+
+impl BinaryCopyInStream {
+    /// Creates a stream from a row-writing closure.
+    ///
+    /// `f` is invoked with a `BinaryCopyInWriter`-like handle which buffers and forwards the
+    /// encoded binary copy rows into the returned stream as they are written.
+    pub fn new<F, T>(types: &[Type], f: F) -> BinaryCopyInStream
+    where
+        F: FnOnce(ChannelBinaryCopyInWriter) -> T + Send + 'static,
+        T: Future<Output = Result<(), Error>> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel(1);
+        let writer = ChannelBinaryCopyInWriter {
+            sender,
+            types: types.to_vec(),
+            buf: header(),
+        };
+        let driver = Box::pin(f(writer));
+
+        BinaryCopyInStream {
+            receiver,
+            driver,
+            done: false,
+        }
+    }
+}
+
+impl Stream for BinaryCopyInStream {
+    type Item = Result<Bytes, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        if let Poll::Ready(bytes) = this.receiver.as_mut().poll_next(cx) {
+            return Poll::Ready(bytes.map(Ok));
+        }
+
+        if *this.done {
+            return Poll::Ready(None);
+        }
+
+        match ready!(this.driver.as_mut().poll(cx)) {
+            Ok(()) => {
+                *this.done = true;
+                Poll::Ready(None)
+            }
+            Err(e) => {
+                *this.done = true;
+                Poll::Ready(Some(Err(e)))
+            }
+        }
+    }
+}
+
+/// A handle used to write rows into a `BinaryCopyInStream`.
+pub struct ChannelBinaryCopyInWriter {
+    sender: mpsc::Sender<Bytes>,
+    types: Vec<Type>,
+    buf: BytesMut,
+}
+
+impl ChannelBinaryCopyInWriter {
+    /// Writes a single row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values provided does not match the number of types the writer was
+    /// created with.
+    pub async fn write(&mut self, values: &[&(dyn ToSql + Sync)]) -> Result<(), Error> {
+        self.write_raw(values.iter().copied()).await
+    }
+
+    /// A maximally-flexible version of `write`, accepting any iterator of `ToSql`-borrowable
+    /// values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values provided does not match the number of types the writer was
+    /// created with.
+    pub async fn write_raw<P, I>(&mut self, values: I) -> Result<(), Error>
+    where
+        P: BorrowToSql,
+        I: IntoIterator<Item = P>,
+    {
+        encode_row(&mut self.buf, &self.types, values)?;
+
+        if self.buf.len() > 4096 {
+            self.flush().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), Error> {
+        let buf = self.buf.split().freeze();
+        self.sender
+            .send(buf)
+            .await
+            .map_err(|_| Error::closed())
+    }
+}
+
+pin_project! {
+    /// A type which serializes rows into the PostgreSQL binary copy format.
+    ///
+    /// The copy *must* be explicitly completed via the `finish` method. If it is not, the copy
+    /// will be aborted.
+    pub struct BinaryCopyInWriter {
+        #[pin]
+        sink: CopyInSink<Bytes>,
+        types: Vec<Type>,
+        buf: BytesMut,
+    }
+}
+
+impl BinaryCopyInWriter {
+    /// Creates a new writer which will write rows of the provided types to the provided sink.
+    pub fn new(sink: CopyInSink<Bytes>, types: &[Type]) -> BinaryCopyInWriter {
+        BinaryCopyInWriter {
+            sink,
+            types: types.to_vec(),
+            buf: header(),
+        }
+    }
+
+    /// Writes a single row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values provided does not match the number of types the writer was
+    /// created with.
+    pub async fn write(self: Pin<&mut Self>, values: &[&(dyn ToSql + Sync)]) -> Result<(), Error> {
+        self.write_raw(values.iter().copied()).await
+    }
+
+    /// A maximally-flexible version of `write`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values provided does not match the number of types the writer was
+    /// created with.
+    pub async fn write_raw<P, I>(mut self: Pin<&mut Self>, values: I) -> Result<(), Error>
+    where
+        P: BorrowToSql,
+        I: IntoIterator<Item = P>,
+    {
+        {
+            let this = self.as_mut().project();
+            encode_row(this.buf, this.types, values)?;
+        }
+
+        if self.buf.len() > 4096 {
+            self.as_mut().flush().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn flush(self: Pin<&mut Self>) -> Result<(), Error> {
+        let this = self.project();
+        let buf = this.buf.split().freeze();
+        this.sink.send(buf).await
+    }
+
+    /// Completes the copy, returning the number of rows added.
+    ///
+    /// This method *must* be used to complete the copy process. If it is not, the copy will be
+    /// aborted.
+    pub async fn finish(mut self: Pin<&mut Self>) -> Result<u64, Error> {
+        self.as_mut().project().buf.put_i16(-1);
+        self.as_mut().flush().await?;
+        self.project().sink.await
+    }
+}
+
+fn header() -> BytesMut {
+    let mut buf = BytesMut::with_capacity(HEADER_LEN);
+    buf.put_slice(MAGIC);
+    buf.put_i32(0); // flags
+    buf.put_i32(0); // header extension length
+    buf
+}
+
+fn encode_row<P, I>(buf: &mut BytesMut, types: &[Type], values: I) -> Result<(), Error>
+where
+    P: BorrowToSql,
+    I: IntoIterator<Item = P>,
+{
+    let values = values.into_iter();
+
+    assert!(
+        values.size_hint().1 == Some(types.len()),
+        "expected {} values",
+        types.len()
+    );
+
+    buf.put_i16(types.len() as i16);
+
+    let mut count = 0;
+    for (value, type_) in values.zip(types) {
+        count += 1;
+        let idx = buf.len();
+        buf.put_i32(0);
+        let len = match value
+            .borrow_to_sql()
+            .to_sql_checked(type_, buf)
+            .map_err(Error::to_sql)?
+        {
+            IsNull::Yes => -1,
+            IsNull::No => {
+                i32::try_from(buf.len() - idx - 4).map_err(|e| Error::encode(Box::new(e)))?
+            }
+        };
+        BigEndian::write_i32(&mut buf[idx..], len);
+    }
+
+    assert!(count == types.len(), "expected {} values", types.len());
+
+    Ok(())
+}
+
+pin_project! {
+    /// A stream of rows deserialized from the PostgreSQL binary copy format.
+    pub struct BinaryCopyOutStream {
+        #[pin]
+        stream: CopyOutStream,
+        types: Vec<Type>,
+    }
+}
+
+impl BinaryCopyOutStream {
+    /// Creates a stream from a raw copy out stream and the types of the columns being returned.
+    pub fn new(types: &[Type], stream: CopyOutStream) -> BinaryCopyOutStream {
+        BinaryCopyOutStream {
+            stream,
+            types: types.to_vec(),
+        }
+    }
+}
+
+impl Stream for BinaryCopyOutStream {
+    type Item = Result<BinaryCopyOutRow, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        let chunk = match ready!(this.stream.poll_next(cx)) {
+            Some(Ok(chunk)) => chunk,
+            Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+            None => return Poll::Ready(Some(Err(Error::closed()))),
+        };
+
+        let row = BinaryCopyOutRow::parse(&chunk, this.types);
+        Poll::Ready(row.map(Ok))
+    }
+}
+
+/// A row decoded from a `BinaryCopyOutStream`.
+pub struct BinaryCopyOutRow {
+    buf: Bytes,
+    ranges: Vec<Option<std::ops::Range<usize>>>,
+    types: Vec<Type>,
+}
+
+impl BinaryCopyOutRow {
+    fn parse(mut buf: &[u8], types: &[Type]) -> Option<BinaryCopyOutRow> {
+        if buf.starts_with(MAGIC) {
+            let extension_len = BigEndian::read_i32(&buf[MAGIC.len() + 4..]) as usize;
+            buf = &buf[HEADER_LEN + extension_len..];
+        }
+
+        let field_count = buf.get_i16();
+        if field_count == -1 {
+            return None;
+        }
+
+        let buf = Bytes::copy_from_slice(buf);
+        let mut ranges = Vec::with_capacity(field_count as usize);
+        let mut pos = 0;
+        for _ in 0..field_count {
+            let len = BigEndian::read_i32(&buf[pos..]);
+            pos += 4;
+            if len < 0 {
+                ranges.push(None);
+            } else {
+                let len = len as usize;
+                ranges.push(Some(pos..pos + len));
+                pos += len;
+            }
+        }
+
+        Some(BinaryCopyOutRow {
+            buf,
+            ranges,
+            types: types.to_vec(),
+        })
+    }
+
+    /// Returns the value of the specified column.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the column index is out of bounds or if the value cannot be decoded as the
+    /// requested type.
+    pub fn get<'a, T>(&'a self, idx: usize) -> T
+    where
+        T: FromSql<'a>,
+    {
+        match self.try_get(idx) {
+            Ok(value) => value,
+            Err(e) => panic!("error retrieving column {}: {}", idx, e),
+        }
+    }
+
+    /// Like `get`, but returns a `Result` rather than panicking.
+    pub fn try_get<'a, T>(&'a self, idx: usize) -> Result<T, Box<dyn std::error::Error + Sync + Send>>
+    where
+        T: FromSql<'a>,
+    {
+        let type_ = self
+            .types
+            .get(idx)
+            .ok_or_else(|| format!("no column at index {}", idx))?;
+        if !T::accepts(type_) {
+            return Err(Box::new(WrongType::new::<T>(type_.clone())));
+        }
+
+        match &self.ranges[idx] {
+            Some(range) => T::from_sql(type_, &self.buf[range.clone()]),
+            None => T::from_sql_null(type_),
+        }
+    }
+}
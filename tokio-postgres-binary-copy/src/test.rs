@@ -1,7 +1,7 @@
-use crate::{BinaryCopyInStream, BinaryCopyOutStream};
+use crate::{BinaryCopyInStream, BinaryCopyInWriter, BinaryCopyOutStream};
+use futures::{pin_mut, TryStreamExt};
 use tokio_postgres::types::Type;
 use tokio_postgres::{Client, NoTls};
-use futures::TryStreamExt;
 
 async fn connect() -> Client {
     let (client, connection) =
@@ -48,6 +48,41 @@ async fn write_basic() {
     assert_eq!(rows[1].get::<_, Option<&str>>(1), None);
 }
 
+#[tokio::test]
+async fn write_via_writer() {
+    let client = connect().await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INT, bar TEXT)")
+        .await
+        .unwrap();
+
+    let sink = client
+        .copy_in("COPY foo (id, bar) FROM STDIN BINARY", &[])
+        .await
+        .unwrap();
+    let writer = BinaryCopyInWriter::new(sink, &[Type::INT4, Type::TEXT]);
+    pin_mut!(writer);
+    writer.as_mut().write(&[&1i32, &"foobar"]).await.unwrap();
+    writer
+        .as_mut()
+        .write(&[&2i32, &None::<&str>])
+        .await
+        .unwrap();
+    let rows = writer.finish().await.unwrap();
+    assert_eq!(rows, 2);
+
+    let rows = client
+        .query("SELECT id, bar FROM foo ORDER BY id", &[])
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+    assert_eq!(rows[0].get::<_, Option<&str>>(1), Some("foobar"));
+    assert_eq!(rows[1].get::<_, i32>(0), 2);
+    assert_eq!(rows[1].get::<_, Option<&str>>(1), None);
+}
+
 #[tokio::test]
 async fn write_many_rows() {
     let client = connect().await;
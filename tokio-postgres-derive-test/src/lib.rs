@@ -0,0 +1,76 @@
+#![cfg(test)]
+
+use futures::TryStreamExt;
+use tokio_postgres::{Client, FromRow, NoTls};
+
+#[derive(Debug, PartialEq, FromRow)]
+struct Nested {
+    #[postgres(rename = "nested_value")]
+    value: i32,
+}
+
+#[derive(Debug, PartialEq, FromRow)]
+struct Person {
+    #[postgres(rename = "full_name")]
+    name: String,
+    #[postgres(flatten)]
+    nested: Nested,
+}
+
+async fn connect() -> Client {
+    let (client, connection) =
+        tokio_postgres::connect("host=localhost port=5433 user=postgres", NoTls)
+            .await
+            .unwrap();
+    tokio::spawn(async {
+        connection.await.unwrap();
+    });
+    client
+}
+
+#[tokio::test]
+async fn derive_from_row() {
+    let client = connect().await;
+
+    let row = client
+        .query_one(
+            "SELECT 'Bob'::TEXT AS full_name, 1::INT4 AS nested_value",
+            &[],
+        )
+        .await
+        .unwrap();
+
+    let person = Person::from_row(&row).unwrap();
+    assert_eq!(
+        person,
+        Person {
+            name: "Bob".to_string(),
+            nested: Nested { value: 1 },
+        }
+    );
+}
+
+#[tokio::test]
+async fn map_row() {
+    let client = connect().await;
+
+    let people = client
+        .query_raw(
+            "SELECT 'Bob'::TEXT AS full_name, 1::INT4 AS nested_value",
+            std::iter::empty::<&(dyn tokio_postgres::types::ToSql + Sync)>(),
+        )
+        .await
+        .unwrap()
+        .map_row::<Person>()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        people,
+        vec![Person {
+            name: "Bob".to_string(),
+            nested: Nested { value: 1 },
+        }]
+    );
+}
@@ -0,0 +1,56 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields};
+
+use crate::util::parse_field_attrs;
+
+pub fn expand_derive_fromrow(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input,
+                    "#[derive(FromRow)] requires a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input,
+                "#[derive(FromRow)] can only be applied to structs",
+            ))
+        }
+    };
+
+    let mut field_inits = Vec::with_capacity(fields.len());
+    for field in fields {
+        let field_ident = field.ident.as_ref().unwrap();
+        let attrs = parse_field_attrs(&field.attrs)?;
+
+        let init = if attrs.flatten {
+            quote! {
+                #field_ident: tokio_postgres::FromRow::from_row(row)?
+            }
+        } else {
+            let column = attrs.rename.unwrap_or_else(|| field_ident.to_string());
+            quote! {
+                #field_ident: row.try_get(#column)?
+            }
+        };
+        field_inits.push(init);
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics tokio_postgres::FromRow for #ident #ty_generics #where_clause {
+            fn from_row(row: &tokio_postgres::Row) -> ::std::result::Result<Self, tokio_postgres::Error> {
+                ::std::result::Result::Ok(#ident {
+                    #(#field_inits,)*
+                })
+            }
+        }
+    })
+}
@@ -0,0 +1,23 @@
+//! An implementation detail of `tokio-postgres`.
+#![recursion_limit = "256"]
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod from_row;
+mod util;
+
+/// Derives an implementation of `tokio_postgres::FromRow`.
+///
+/// Each struct field is matched to a column of the same name, fetched via `Row::try_get`. A
+/// field's column name can be overridden with `#[postgres(rename = "...")]`, and a field whose
+/// type itself derives `FromRow` can be populated from the same row with `#[postgres(flatten)]`.
+#[proc_macro_derive(FromRow, attributes(postgres))]
+pub fn derive_from_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    from_row::expand_derive_fromrow(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
@@ -0,0 +1,47 @@
+use syn::{Attribute, Lit, Meta, NestedMeta};
+
+/// The parsed contents of a field's `#[postgres(...)]` attribute.
+#[derive(Default)]
+pub struct FieldAttrs {
+    pub rename: Option<String>,
+    pub flatten: bool,
+}
+
+pub fn parse_field_attrs(attrs: &[Attribute]) -> syn::Result<FieldAttrs> {
+    let mut out = FieldAttrs::default();
+
+    for attr in attrs {
+        if !attr.path.is_ident("postgres") {
+            continue;
+        }
+
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(syn::Error::new_spanned(meta, "expected #[postgres(...)]")),
+        };
+
+        for item in list.nested {
+            match item {
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename") => {
+                    match nv.lit {
+                        Lit::Str(s) => out.rename = Some(s.value()),
+                        lit => {
+                            return Err(syn::Error::new_spanned(lit, "rename must be a string"))
+                        }
+                    }
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("flatten") => {
+                    out.flatten = true;
+                }
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        "unsupported #[postgres(...)] attribute",
+                    ))
+                }
+            }
+        }
+    }
+
+    Ok(out)
+}
@@ -0,0 +1,12 @@
+use crate::{Error, Row};
+
+/// A trait implemented by types that can be constructed from a single database `Row`.
+///
+/// This is typically implemented via `#[derive(FromRow)]` rather than by hand: the derive
+/// matches each struct field to a column of the same name (overridable with
+/// `#[postgres(rename = "...")]`), calling `Row::try_get` for each, and recurses into nested
+/// structs marked with `#[postgres(flatten)]`.
+pub trait FromRow: Sized {
+    /// Creates a new value of this type from a row.
+    fn from_row(row: &Row) -> Result<Self, Error>;
+}
@@ -1,31 +1,42 @@
 use crate::client::{InnerClient, Responses};
 use crate::codec::FrontendMessage;
 use crate::connection::RequestMessages;
-use crate::types::{IsNull, ToSql};
-use crate::{Error, Portal, Row, Statement};
+use crate::types::{BorrowToSql, IsNull, ToSql};
+use crate::{Error, FromRow, Portal, Row, Statement};
 use bytes::{Bytes, BytesMut};
 use futures::{ready, Stream};
+use log::{debug, log_enabled, Level};
 use pin_project_lite::pin_project;
 use postgres_protocol::message::backend::Message;
 use postgres_protocol::message::frontend;
-use std::marker::PhantomPinned;
+use std::fmt;
+use std::marker::{PhantomData, PhantomPinned};
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
-pub async fn query<'a, I>(
+pub async fn query<P, I>(
     client: &InnerClient,
     statement: Statement,
     params: I,
 ) -> Result<RowStream, Error>
 where
-    I: IntoIterator<Item = &'a dyn ToSql>,
+    P: BorrowToSql,
+    I: IntoIterator<Item = P>,
     I::IntoIter: ExactSizeIterator,
 {
-    let buf = encode(client, &statement, params)?;
+    let buf = if log_enabled!(Level::Debug) {
+        let params = params.into_iter().collect::<Vec<_>>();
+        log_statement(&statement, &params);
+        encode(client, &statement, params)?
+    } else {
+        encode(client, &statement, params)?
+    };
     let responses = start(client, buf).await?;
     Ok(RowStream {
         statement,
         responses,
+        command_tag: None,
+        rows_affected: None,
         _p: PhantomPinned,
     })
 }
@@ -46,20 +57,29 @@ pub async fn query_portal(
     Ok(RowStream {
         statement: portal.statement().clone(),
         responses,
+        command_tag: None,
+        rows_affected: None,
         _p: PhantomPinned,
     })
 }
 
-pub async fn execute<'a, I>(
+pub async fn execute<P, I>(
     client: &InnerClient,
     statement: Statement,
     params: I,
 ) -> Result<u64, Error>
 where
-    I: IntoIterator<Item = &'a dyn ToSql>,
+    P: BorrowToSql,
+    I: IntoIterator<Item = P>,
     I::IntoIter: ExactSizeIterator,
 {
-    let buf = encode(client, &statement, params)?;
+    let buf = if log_enabled!(Level::Debug) {
+        let params = params.into_iter().collect::<Vec<_>>();
+        log_statement(&statement, &params);
+        encode(client, &statement, params)?
+    } else {
+        encode(client, &statement, params)?
+    };
     let mut responses = start(client, buf).await?;
 
     loop {
@@ -82,6 +102,32 @@ where
     }
 }
 
+// `Statement` only retains the server-side prepared name, not the original SQL text, so this
+// logs the name rather than the query string.
+fn log_statement<P>(statement: &Statement, params: &[P])
+where
+    P: BorrowToSql,
+{
+    debug!(
+        "executing statement {} with parameters: {:?}",
+        statement.name(),
+        BorrowToSqlParamsDebug(params)
+    );
+}
+
+struct BorrowToSqlParamsDebug<'a, P>(&'a [P]);
+
+impl<P> fmt::Debug for BorrowToSqlParamsDebug<'_, P>
+where
+    P: BorrowToSql,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_list()
+            .entries(self.0.iter().map(BorrowToSql::borrow_to_sql))
+            .finish()
+    }
+}
+
 async fn start(client: &InnerClient, buf: Bytes) -> Result<Responses, Error> {
     let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
 
@@ -93,9 +139,10 @@ async fn start(client: &InnerClient, buf: Bytes) -> Result<Responses, Error> {
     Ok(responses)
 }
 
-pub fn encode<'a, I>(client: &InnerClient, statement: &Statement, params: I) -> Result<Bytes, Error>
+pub fn encode<P, I>(client: &InnerClient, statement: &Statement, params: I) -> Result<Bytes, Error>
 where
-    I: IntoIterator<Item = &'a dyn ToSql>,
+    P: BorrowToSql,
+    I: IntoIterator<Item = P>,
     I::IntoIter: ExactSizeIterator,
 {
     client.with_buf(|buf| {
@@ -106,14 +153,15 @@ where
     })
 }
 
-pub fn encode_bind<'a, I>(
+pub fn encode_bind<P, I>(
     statement: &Statement,
     params: I,
     portal: &str,
     buf: &mut BytesMut,
 ) -> Result<(), Error>
 where
-    I: IntoIterator<Item = &'a dyn ToSql>,
+    P: BorrowToSql,
+    I: IntoIterator<Item = P>,
     I::IntoIter: ExactSizeIterator,
 {
     let params = params.into_iter();
@@ -131,7 +179,7 @@ where
         statement.name(),
         Some(1),
         params.zip(statement.params()).enumerate(),
-        |(idx, (param, ty)), buf| match param.to_sql_checked(ty, buf) {
+        |(idx, (param, ty)), buf| match param.borrow_to_sql().to_sql_checked(ty, buf) {
             Ok(IsNull::No) => Ok(postgres_protocol::IsNull::No),
             Ok(IsNull::Yes) => Ok(postgres_protocol::IsNull::Yes),
             Err(e) => {
@@ -154,6 +202,8 @@ pin_project! {
     pub struct RowStream {
         statement: Statement,
         responses: Responses,
+        command_tag: Option<String>,
+        rows_affected: Option<u64>,
         #[pin]
         _p: PhantomPinned,
     }
@@ -168,11 +218,69 @@ impl Stream for RowStream {
             Message::DataRow(body) => {
                 Poll::Ready(Some(Ok(Row::new(this.statement.clone(), body)?)))
             }
-            Message::EmptyQueryResponse
-            | Message::CommandComplete(_)
-            | Message::PortalSuspended => Poll::Ready(None),
+            Message::CommandComplete(body) => {
+                let tag = body.tag().map_err(Error::parse)?;
+                *this.rows_affected = Some(tag.rsplit(' ').next().unwrap().parse().unwrap_or(0));
+                *this.command_tag = Some(tag.to_string());
+                Poll::Ready(None)
+            }
+            Message::EmptyQueryResponse | Message::PortalSuspended => Poll::Ready(None),
             Message::ErrorResponse(body) => Poll::Ready(Some(Err(Error::db(body)))),
             _ => Poll::Ready(Some(Err(Error::unexpected_message()))),
         }
     }
 }
+
+impl RowStream {
+    /// Returns the command tag of this query.
+    ///
+    /// This is only available after the stream has been exhausted.
+    pub fn command_tag(&self) -> Option<&str> {
+        self.command_tag.as_deref()
+    }
+
+    /// Returns the number of rows affected, if applicable.
+    ///
+    /// This is only available after the stream has been exhausted.
+    pub fn rows_affected(&self) -> Option<u64> {
+        self.rows_affected
+    }
+
+    /// Maps each `Row` yielded by this stream into `T` via `FromRow`.
+    pub fn map_row<T>(self) -> MapRow<T>
+    where
+        T: FromRow,
+    {
+        MapRow {
+            stream: self,
+            _p: PhantomData,
+        }
+    }
+}
+
+pin_project! {
+    /// A stream of rows mapped into a user type via `FromRow`.
+    ///
+    /// Returned by `RowStream::map_row`.
+    pub struct MapRow<T> {
+        #[pin]
+        stream: RowStream,
+        _p: PhantomData<T>,
+    }
+}
+
+impl<T> Stream for MapRow<T>
+where
+    T: FromRow,
+{
+    type Item = Result<T, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        match ready!(this.stream.poll_next(cx)) {
+            Some(Ok(row)) => Poll::Ready(Some(T::from_row(&row))),
+            Some(Err(e)) => Poll::Ready(Some(Err(e))),
+            None => Poll::Ready(None),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use crate::types::ToSql;
+use crate::NoTls;
+use futures::{pin_mut, StreamExt};
+
+async fn connect() -> crate::Client {
+    let (client, connection) =
+        crate::connect("host=localhost port=5433 user=postgres", NoTls)
+            .await
+            .unwrap();
+    tokio::spawn(async {
+        connection.await.unwrap();
+    });
+    client
+}
+
+#[tokio::test]
+async fn row_stream_command_tag_and_rows_affected() {
+    let client = connect().await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INT)")
+        .await
+        .unwrap();
+    client
+        .batch_execute("INSERT INTO foo (id) VALUES (1), (2), (3)")
+        .await
+        .unwrap();
+
+    let stream = client
+        .query_raw("SELECT id FROM foo", std::iter::empty::<&dyn ToSql>())
+        .await
+        .unwrap();
+    pin_mut!(stream);
+
+    assert_eq!(stream.command_tag(), None);
+    assert_eq!(stream.rows_affected(), None);
+
+    let mut rows = 0;
+    while stream.next().await.transpose().unwrap().is_some() {
+        rows += 1;
+    }
+    assert_eq!(rows, 3);
+
+    assert_eq!(stream.command_tag(), Some("SELECT 3"));
+    assert_eq!(stream.rows_affected(), Some(3));
+}
+
+#[tokio::test]
+async fn query_and_execute_accept_owned_and_boxed_params() {
+    let client = connect().await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INT, bar TEXT)")
+        .await
+        .unwrap();
+
+    client
+        .execute(
+            "INSERT INTO foo (id, bar) VALUES (1, 'a'), (2, 'b')",
+            std::iter::empty::<&dyn ToSql>(),
+        )
+        .await
+        .unwrap();
+
+    // an owned `Vec<i32>` of concrete, non-trait-object values, passed directly without
+    // collecting into `Vec<&dyn ToSql>`
+    let ids: Vec<i32> = vec![1];
+    let rows = client
+        .query("SELECT bar FROM foo WHERE id = $1", ids)
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<_, &str>(0), "a");
+
+    // a dynamic list of boxed `ToSql` values, also passed without trait-object references
+    let params: Vec<Box<dyn ToSql + Sync>> = vec![Box::new(2i32)];
+    let rows = client
+        .query("SELECT bar FROM foo WHERE id = $1", params)
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<_, &str>(0), "b");
+}
@@ -0,0 +1,34 @@
+use crate::types::ToSql;
+
+/// A trait used to borrow parameters to `ToSql` trait objects without requiring callers to
+/// first collect them into a `Vec<&dyn ToSql>`.
+///
+/// This allows the `query`, `execute`, and related APIs to accept owned values (e.g. a
+/// `Vec<i32>`) or slices of a concrete type directly, in addition to the traditional
+/// `&[&dyn ToSql]`.
+pub trait BorrowToSql {
+    /// Returns a reference to `self` as a `ToSql` trait object.
+    fn borrow_to_sql(&self) -> &dyn ToSql;
+}
+
+impl BorrowToSql for &dyn ToSql {
+    fn borrow_to_sql(&self) -> &dyn ToSql {
+        *self
+    }
+}
+
+impl BorrowToSql for &(dyn ToSql + Sync) {
+    fn borrow_to_sql(&self) -> &dyn ToSql {
+        *self
+    }
+}
+
+impl<T> BorrowToSql for T
+where
+    T: ToSql,
+{
+    fn borrow_to_sql(&self) -> &dyn ToSql {
+        // covers owned values as well as `Box<dyn ToSql + Sync>`, which implements `ToSql`
+        self
+    }
+}